@@ -6,8 +6,11 @@ use crate::error::{Error, Result};
 use crate::intparse::{self, Integer};
 use crate::lines::{DefIter, Define, LineIter};
 use serde::de::{
-    self, Deserialize, DeserializeSeed, MapAccess, SeqAccess, Visitor,
+    self, Deserialize, DeserializeOwned, DeserializeSeed, IntoDeserializer,
+    MapAccess, SeqAccess, Visitor,
 };
+use std::fmt;
+use std::io;
 use std::iter::Peekable;
 
 /// Iterator for key/value mappings
@@ -54,17 +57,160 @@ impl<'a> MappingIter<'a> {
             None => Err(Error::Eof),
         }
     }
+
+    /// Peek the current value, without consuming it
+    fn peek_value(&mut self) -> Result<&'a str> {
+        match self.defs.peek() {
+            Some(Define::Invalid(e, ln)) => {
+                Err(Error::FailedParse(format!("{:?} {}", e, ln)))
+            }
+            Some(Define::Valid(_, _, _, v)) => Ok(v),
+            None => Err(Error::Eof),
+        }
+    }
+
+    /// Peek the indent of the current define, if any
+    fn peek_indent(&mut self) -> Option<usize> {
+        match self.defs.peek() {
+            Some(Define::Valid(indent, _, _, _)) => Some(*indent),
+            _ => None,
+        }
+    }
+
+    /// Peek whether the current define is an append continuation
+    fn peek_append(&mut self) -> Option<bool> {
+        match self.defs.peek() {
+            Some(Define::Valid(_, append, _, _)) => Some(*append),
+            _ => None,
+        }
+    }
+}
+
+/// A schema-less MuON value, produced by `deserialize_any`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// An absent value
+    Null,
+    /// A boolean value
+    Bool(bool),
+    /// An integer value
+    Int(i64),
+    /// A floating-point value
+    Float(f64),
+    /// A text value
+    Text(String),
+    /// A space-separated list of values
+    List(Vec<Value>),
+    /// A mapping of keys to values
+    Map(Vec<(String, Value)>),
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Builds a [`Value`] from whatever `deserialize_any` finds
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a valid MuON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> std::result::Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<Value, E> {
+        Ok(Value::Int(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Value, E> {
+        Ok(Value::Int(v as i64))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> std::result::Result<Value, E> {
+        Ok(Value::Float(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<Value, E> {
+        Ok(Value::Text(v.to_string()))
+    }
+
+    fn visit_borrowed_str<E>(
+        self,
+        v: &'de str,
+    ) -> std::result::Result<Value, E> {
+        Ok(Value::Text(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> std::result::Result<Value, E> {
+        Ok(Value::Text(v))
+    }
+
+    fn visit_unit<E>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut list = Vec::new();
+        while let Some(v) = seq.next_element()? {
+            list.push(v);
+        }
+        Ok(Value::List(list))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut out = Vec::new();
+        while let Some((k, v)) = map.next_entry::<String, Value>()? {
+            out.push((k, v));
+        }
+        Ok(Value::Map(out))
+    }
 }
 
 /// MuON deserializer
 pub struct Deserializer<'de> {
     mappings: MappingIter<'de>,
+    /// Declared struct fields, and which of them have been matched
+    /// against a real document key so far, set while deserializing a
+    /// known struct (not a schema-less map)
+    fields: Option<(&'static [&'static str], Vec<bool>)>,
+    /// A declared field name queued up by `next_key_seed` once the
+    /// document has no more keys for it, so the following
+    /// `next_value_seed` call can report it as missing rather than
+    /// re-reading whatever real value comes next
+    pending_missing: Option<&'static str>,
+    /// Indent level of the current seq/map's children, if nested
+    indent: Option<usize>,
 }
 
 impl<'de> Deserializer<'de> {
     fn from_str(input: &'de str) -> Self {
         let mappings = MappingIter::new(LineIter::new(input));
-        Deserializer { mappings }
+        Deserializer {
+            mappings,
+            fields: None,
+            pending_missing: None,
+            indent: None,
+        }
     }
 }
 
@@ -75,15 +221,50 @@ where
 {
     let mut deserializer = Deserializer::from_str(s);
     let t = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
     Ok(t)
 }
 
+/// Create a MuON deserializer from an I/O reader
+pub fn from_reader<R, T>(mut reader: R) -> Result<T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    let mut s = String::new();
+    reader.read_to_string(&mut s).map_err(Error::Io)?;
+    from_str(&s)
+}
+
+impl<'de> Deserializer<'de> {
+    /// Check that all input was consumed
+    fn end(&mut self) -> Result<()> {
+        if self.check_key()? {
+            Err(Error::TrailingData)
+        } else {
+            Ok(())
+        }
+    }
+}
+
 impl<'de> Deserializer<'de> {
     /// Check if the key is valid
     fn check_key(&mut self) -> Result<bool> {
         self.mappings.check_key()
     }
 
+    /// Check whether another define remains at (or below) the current
+    /// nesting level, rather than having fallen back to the parent level
+    fn more_at_indent(&mut self) -> Result<bool> {
+        if !self.check_key()? {
+            return Ok(false);
+        }
+        match (self.mappings.peek_indent(), self.indent) {
+            (Some(indent), Some(floor)) => Ok(indent >= floor),
+            _ => Ok(true),
+        }
+    }
+
     /// Peek the current key
     fn peek_key(&mut self) -> Result<&'de str> {
         self.mappings.peek_key()
@@ -94,9 +275,49 @@ impl<'de> Deserializer<'de> {
         self.mappings.get_value()
     }
 
+    /// Peek the current value, without consuming it
+    fn peek_value(&mut self) -> Result<&'de str> {
+        self.mappings.peek_value()
+    }
+
+    /// Consume the current key, whose value is expected to be empty,
+    /// and check whether a deeper-indented child block immediately
+    /// follows it (as opposed to a sibling or the end of input)
+    fn consume_header_and_check_child(&mut self) -> Result<bool> {
+        let header_indent = self.mappings.peek_indent();
+        self.get_value()?;
+        Ok(match (self.mappings.peek_indent(), header_indent) {
+            (Some(next), Some(header)) => next > header,
+            (Some(_), None) => true,
+            _ => false,
+        })
+    }
+
+    /// Collect any following "append" lines for the given key/indent,
+    /// joining each piece onto `first` with `\n`
+    fn collect_appends(
+        &mut self,
+        key: &'de str,
+        indent: Option<usize>,
+        first: &'de str,
+    ) -> Result<Option<String>> {
+        let mut text: Option<String> = None;
+        while self.mappings.peek_append() == Some(true) {
+            let same_key = self.peek_key().ok() == Some(key);
+            let same_indent = self.mappings.peek_indent() == indent;
+            if !same_key || !same_indent {
+                return Err(Error::ExpectedAppend);
+            }
+            let piece = self.get_value()?;
+            let s = text.get_or_insert_with(|| first.to_string());
+            s.push('\n');
+            s.push_str(piece);
+        }
+        Ok(text)
+    }
+
     fn parse_text(&mut self) -> Result<&'de str> {
-        // FIXME: in a list, get one value only
-        Ok(self.get_value()?)
+        self.get_value()
     }
 
     fn parse_char(&mut self) -> Result<char> {
@@ -126,17 +347,139 @@ impl<'de> Deserializer<'de> {
             Err(Error::ExpectedInteger)
         }
     }
+
+    fn parse_float<T: Float>(&mut self) -> Result<T> {
+        let value = self.get_value()?;
+        match value {
+            "inf" | "+inf" => Ok(T::from_f64(f64::INFINITY)),
+            "-inf" => Ok(T::from_f64(f64::NEG_INFINITY)),
+            "NaN" => Ok(T::from_f64(f64::NAN)),
+            _ if is_float_syntax(value) => match value.parse::<f64>() {
+                Ok(v) => Ok(T::from_f64(v)),
+                Err(_) => Err(Error::ExpectedFloat),
+            },
+            _ => Err(Error::ExpectedFloat),
+        }
+    }
+}
+
+/// Target type for [`Deserializer::parse_float`]
+trait Float: Sized {
+    /// Convert from a parsed `f64`
+    fn from_f64(v: f64) -> Self;
+}
+
+impl Float for f32 {
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+}
+
+impl Float for f64 {
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+}
+
+/// Check that a value contains only valid MuON float syntax: an optional
+/// sign, an integer part and/or a fractional part (at least one digit in
+/// all), and an optional `e`/`E` exponent
+fn is_float_syntax(value: &str) -> bool {
+    let mut chars = value.chars().peekable();
+    if let Some('+') | Some('-') = chars.peek() {
+        chars.next();
+    }
+    let mut has_digits = false;
+    while let Some(c) = chars.peek() {
+        if c.is_ascii_digit() {
+            has_digits = true;
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    if chars.peek() == Some(&'.') {
+        chars.next();
+        while let Some(c) = chars.peek() {
+            if c.is_ascii_digit() {
+                has_digits = true;
+                chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+    if !has_digits {
+        return false;
+    }
+    if let Some('e') | Some('E') = chars.peek() {
+        chars.next();
+        if let Some('+') | Some('-') = chars.peek() {
+            chars.next();
+        }
+        let mut exp_digits = false;
+        while let Some(c) = chars.peek() {
+            if c.is_ascii_digit() {
+                exp_digits = true;
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        if !exp_digits {
+            return false;
+        }
+    }
+    chars.peek().is_none()
 }
 
 impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     type Error = Error;
 
-    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        // FIXME: use schema to know what types to return
-        unimplemented!();
+        let value = self.peek_value()?;
+        if value.is_empty() {
+            // an empty value only means the key is a header for an
+            // indented child block if one is actually there; otherwise
+            // it's just an absent scalar
+            return if self.consume_header_and_check_child()? {
+                self.deserialize_map(visitor)
+            } else {
+                visitor.visit_unit()
+            };
+        }
+        if value.contains(' ') {
+            // each space-separated item is visited as a string
+            let value = self.get_value()?;
+            let items = value.split(' ').filter(|item| !item.is_empty());
+            let mut seq = de::value::SeqDeserializer::<_, Error>::new(items);
+            let list = visitor.visit_seq(&mut seq)?;
+            seq.end()?;
+            return Ok(list);
+        }
+        let value = self.get_value()?;
+        match value {
+            "true" => visitor.visit_bool(true),
+            "false" => visitor.visit_bool(false),
+            "inf" | "+inf" => visitor.visit_f64(f64::INFINITY),
+            "-inf" => visitor.visit_f64(f64::NEG_INFINITY),
+            "NaN" => visitor.visit_f64(f64::NAN),
+            _ => {
+                if let Some(v) = intparse::from_str::<i64>(value) {
+                    visitor.visit_i64(v)
+                } else if is_float_syntax(value) {
+                    match value.parse::<f64>() {
+                        Ok(v) => visitor.visit_f64(v),
+                        Err(_) => visitor.visit_borrowed_str(value),
+                    }
+                } else {
+                    visitor.visit_borrowed_str(value)
+                }
+            }
+        }
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
@@ -211,18 +554,18 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         visitor.visit_u64(self.parse_int()?)
     }
 
-    fn deserialize_f32<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        visitor.visit_f32(self.parse_float()?)
     }
 
-    fn deserialize_f64<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        visitor.visit_f64(self.parse_float()?)
     }
 
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
@@ -236,8 +579,13 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        // FIXME: if next line is an "append", build a temp String
-        visitor.visit_borrowed_str(self.parse_text()?)
+        let key = self.peek_key()?;
+        let indent = self.mappings.peek_indent();
+        let first = self.parse_text()?;
+        match self.collect_appends(key, indent, first)? {
+            Some(text) => visitor.visit_string(text),
+            None => visitor.visit_borrowed_str(first),
+        }
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
@@ -265,8 +613,17 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        // FIXME
-        visitor.visit_some(self)
+        if !self.check_key()? {
+            return visitor.visit_none();
+        }
+        if !self.peek_value()?.is_empty() {
+            return visitor.visit_some(self);
+        }
+        if self.consume_header_and_check_child()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
@@ -302,7 +659,11 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(self)
+        let parent_indent = self.indent.take();
+        self.indent = self.mappings.peek_indent();
+        let value = visitor.visit_seq(&mut *self)?;
+        self.indent = parent_indent;
+        Ok(value)
     }
 
     fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
@@ -328,31 +689,44 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_map(self)
+        let parent_fields = self.fields.take();
+        let parent_indent = self.indent.take();
+        self.indent = self.mappings.peek_indent();
+        let value = visitor.visit_map(&mut *self)?;
+        self.fields = parent_fields;
+        self.indent = parent_indent;
+        Ok(value)
     }
 
     fn deserialize_struct<V>(
         self,
         _name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_map(visitor)
+        let seen = vec![false; fields.len()];
+        let parent_fields = self.fields.replace((fields, seen));
+        let parent_indent = self.indent.take();
+        self.indent = self.mappings.peek_indent();
+        let value = visitor.visit_map(&mut *self)?;
+        self.fields = parent_fields;
+        self.indent = parent_indent;
+        Ok(value)
     }
 
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
         _variants: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ExpectedEnum)
+        visitor.visit_enum(self)
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
@@ -370,8 +744,7 @@ impl<'de> SeqAccess<'de> for Deserializer<'de> {
     where
         T: DeserializeSeed<'de>,
     {
-        // FIXME: check for more at this indent level
-        if self.check_key()? {
+        if self.more_at_indent()? {
             seed.deserialize(&mut *self).map(Some)
         } else {
             Ok(None)
@@ -386,20 +759,124 @@ impl<'de> MapAccess<'de> for Deserializer<'de> {
     where
         K: DeserializeSeed<'de>,
     {
-        // FIXME: check for more at this indent level
-        if self.check_key()? {
-            seed.deserialize(&mut *self).map(Some)
-        } else {
-            Ok(None)
+        if self.more_at_indent()? {
+            // a real key is next: match it against the declared fields
+            // by name (in whatever order the document actually has it),
+            // marking that field as accounted for either way
+            let key = self.peek_key()?;
+            if let Some((fields, seen)) = &mut self.fields {
+                if let Some(idx) = fields.iter().position(|f| *f == key) {
+                    seen[idx] = true;
+                }
+            }
+            // the key is only peeked, so its value is left for
+            // `next_value_seed`
+            let key = de::value::BorrowedStrDeserializer::<Error>::new(key);
+            return seed.deserialize(key).map(Some);
         }
+        // no more real keys in this block; synthesize a MissingField
+        // for each declared field that was never matched above
+        if let Some((fields, seen)) = &mut self.fields {
+            if let Some(idx) = seen.iter().position(|s| !s) {
+                seen[idx] = true;
+                let name = fields[idx];
+                self.pending_missing = Some(name);
+                return seed.deserialize(name.into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
     where
         V: DeserializeSeed<'de>,
+    {
+        if let Some(name) = self.pending_missing.take() {
+            seed.deserialize(MissingField(name))
+        } else {
+            seed.deserialize(&mut *self)
+        }
+    }
+}
+
+/// A stand-in deserializer for a struct field whose key is absent from
+/// the document, so that `Option<T>` fields deserialize to `None`
+/// instead of mismatching against the next key actually present
+struct MissingField(&'static str);
+
+impl<'de> de::Deserializer<'de> for MissingField {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::custom(format!("missing field `{}`", self.0)))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_none()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for &'a mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let name = match self.get_value() {
+            Ok(v) if !v.is_empty() => v,
+            Ok(_) | Err(Error::Eof) => return Err(Error::ExpectedEnum),
+            Err(e) => return Err(e),
+        };
+        let value = seed.deserialize(name.into_deserializer())?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for &'a mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
     {
         seed.deserialize(&mut *self)
     }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
 }
 
 #[cfg(test)]
@@ -442,4 +919,262 @@ mod test {
         assert_eq!(expected, from_str(b)?);
         Ok(())
     }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Temp {
+        c: f64,
+    }
+
+    #[test]
+    fn float_basic() -> Result<(), Box<Error>> {
+        let t = "c: -12.5\n";
+        assert_eq!(Temp { c: -12.5 }, from_str(t)?);
+        Ok(())
+    }
+
+    #[test]
+    fn float_special() -> Result<(), Box<Error>> {
+        let t: Temp = from_str("c: inf\n")?;
+        assert_eq!(t.c, f64::INFINITY);
+        let t: Temp = from_str("c: +inf\n")?;
+        assert_eq!(t.c, f64::INFINITY);
+        let t: Temp = from_str("c: -inf\n")?;
+        assert_eq!(t.c, f64::NEG_INFINITY);
+        let t: Temp = from_str("c: NaN\n")?;
+        assert!(t.c.is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn float_malformed_is_rejected() {
+        let result: Result<Temp, Error> = from_str("c: 1.2.3\n");
+        assert!(matches!(result, Err(Error::ExpectedFloat)));
+
+        let result: Result<Temp, Error> = from_str("c: 12abc\n");
+        assert!(matches!(result, Err(Error::ExpectedFloat)));
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct TempF32 {
+        c: f32,
+    }
+
+    #[test]
+    fn float_f32() -> Result<(), Box<Error>> {
+        let t = "c: 1.5\n";
+        assert_eq!(TempF32 { c: 1.5 }, from_str(t)?);
+        Ok(())
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Shape {
+        Circle,
+        Square,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct WithShape {
+        shape: Shape,
+    }
+
+    #[test]
+    fn enum_unit_variant() -> Result<(), Box<Error>> {
+        let s = "shape: Square\n";
+        let expected = WithShape {
+            shape: Shape::Square,
+        };
+        assert_eq!(expected, from_str(s)?);
+        Ok(())
+    }
+
+    #[test]
+    fn enum_missing_variant_name() {
+        let s = "shape:\n";
+        let result: Result<WithShape, Error> = from_str(s);
+        assert!(matches!(result, Err(Error::ExpectedEnum)));
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Action {
+        Move(i32),
+        Resize { width: i32, height: i32 },
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct WithAction {
+        action: Action,
+    }
+
+    #[test]
+    fn enum_newtype_variant() -> Result<(), Box<Error>> {
+        let s = "action: Move\n    value: 5\n";
+        let expected = WithAction {
+            action: Action::Move(5),
+        };
+        assert_eq!(expected, from_str(s)?);
+        Ok(())
+    }
+
+    #[test]
+    fn enum_struct_variant() -> Result<(), Box<Error>> {
+        let s = "action: Resize\n    width: 10\n    height: 20\n";
+        let expected = WithAction {
+            action: Action::Resize {
+                width: 10,
+                height: 20,
+            },
+        };
+        assert_eq!(expected, from_str(s)?);
+        Ok(())
+    }
+
+    use super::Value;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct WithValue {
+        v: Value,
+    }
+
+    #[test]
+    fn value_scalar() -> Result<(), Box<Error>> {
+        let d: WithValue = from_str("v: 42\n")?;
+        assert_eq!(d.v, Value::Int(42));
+        let d: WithValue = from_str("v: true\n")?;
+        assert_eq!(d.v, Value::Bool(true));
+        let d: WithValue = from_str("v: hello\n")?;
+        assert_eq!(d.v, Value::Text("hello".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn value_special_float() -> Result<(), Box<Error>> {
+        let d: WithValue = from_str("v: inf\n")?;
+        assert_eq!(d.v, Value::Float(f64::INFINITY));
+        let d: WithValue = from_str("v: NaN\n")?;
+        match d.v {
+            Value::Float(f) => assert!(f.is_nan()),
+            _ => panic!("expected Value::Float"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn value_list() -> Result<(), Box<Error>> {
+        let d: WithValue = from_str("v: a b c\n")?;
+        let expected = Value::List(vec![
+            Value::Text("a".to_string()),
+            Value::Text("b".to_string()),
+            Value::Text("c".to_string()),
+        ]);
+        assert_eq!(d.v, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn value_map() -> Result<(), Box<Error>> {
+        let d: WithValue = from_str("v:\n    x: 1\n    y: 2\n")?;
+        let expected = Value::Map(vec![
+            ("x".to_string(), Value::Int(1)),
+            ("y".to_string(), Value::Int(2)),
+        ]);
+        assert_eq!(d.v, expected);
+        Ok(())
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Note {
+        text: String,
+    }
+
+    #[test]
+    fn append_lines() -> Result<(), Box<Error>> {
+        let n = "text: line one\n+line two\n+line three\n";
+        let expected = Note {
+            text: "line one\nline two\nline three".to_string(),
+        };
+        assert_eq!(expected, from_str(n)?);
+        Ok(())
+    }
+
+    #[test]
+    fn append_mismatched_indent_is_rejected() {
+        let n = "text: line one\n    +line two\n";
+        let result: Result<Note, Error> = from_str(n);
+        assert!(matches!(result, Err(Error::ExpectedAppend)));
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Foo {
+        a: i32,
+        b: i32,
+        c: i32,
+    }
+
+    #[test]
+    fn struct_fields_out_of_order() -> Result<(), Box<Error>> {
+        let f = "c: 3\na: 1\nb: 2\n";
+        let expected = Foo { a: 1, b: 2, c: 3 };
+        assert_eq!(expected, from_str(f)?);
+        Ok(())
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct WithOption {
+        a: i32,
+        b: Option<i32>,
+    }
+
+    #[test]
+    fn option_field_present_and_missing() -> Result<(), Box<Error>> {
+        let present = "a: 1\nb: 2\n";
+        let expected = WithOption { a: 1, b: Some(2) };
+        assert_eq!(expected, from_str(present)?);
+
+        let missing = "a: 1\n";
+        let expected = WithOption { a: 1, b: None };
+        assert_eq!(expected, from_str(missing)?);
+        Ok(())
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Inner {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Outer {
+        sub: Option<Inner>,
+        after: i32,
+    }
+
+    #[test]
+    fn nested_block_terminates_at_parent_indent() -> Result<(), Box<Error>> {
+        let o = "sub:\n    x: 1\n    y: 2\nafter: 3\n";
+        let expected = Outer {
+            sub: Some(Inner { x: 1, y: 2 }),
+            after: 3,
+        };
+        assert_eq!(expected, from_str(o)?);
+        Ok(())
+    }
+
+    #[test]
+    fn reader_roundtrip() -> Result<(), Box<Error>> {
+        let a = "b: false\nuint: 7\nint: -5\n";
+        let expected = A {
+            b: false,
+            uint: 7,
+            int: -5,
+        };
+        assert_eq!(expected, super::from_reader(a.as_bytes())?);
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_data_is_rejected() {
+        let a = "b: false\nuint: 7\nint: -5\nextra: 1\n";
+        let result: Result<A, Error> = from_str(a);
+        assert!(matches!(result, Err(Error::TrailingData)));
+    }
 }